@@ -0,0 +1,179 @@
+//! A small authenticated HTTP control surface so external tooling can drive
+//! the main window and the "Oni session" state remotely, e.g.
+//! `GET /actions?action=session_start` to bring the window to front and
+//! `session_end` to hide it again. Every request must carry the per-launch
+//! bearer token handed out via the `get_control_token` command, so only the
+//! trusted frontend (or a user who copied the token) can command the window.
+//!
+//! This does reopen a discoverable loopback TCP port, which is exactly what
+//! chunk0-6 moved the sidecar off of. That's intentional here rather than an
+//! oversight: this surface exists specifically so *external* tooling (not
+//! just this app's own frontend) can reach it, which an app-private Unix
+//! socket/named pipe wouldn't serve as conveniently, and every request still
+//! has to present the bearer token to do anything.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json};
+use axum::routing::get;
+use axum::Router;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+/// Whether the Oni session is currently considered active (window shown).
+static SESSION_ACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// `127.0.0.1:<port>` the control API ended up bound to, so callers can
+/// discover it the same way they discover the sidecar's `get_ipc_endpoint`.
+static CONTROL_ENDPOINT: Mutex<Option<String>> = Mutex::new(None);
+
+/// The address the control API is listening on, if it has started.
+pub fn endpoint() -> Option<String> {
+    CONTROL_ENDPOINT.lock().unwrap().clone()
+}
+
+#[derive(Clone)]
+struct ControlState {
+    app: AppHandle,
+    token: String,
+}
+
+/// Compare two strings in constant time (w.r.t. their shared length) so a
+/// timing attack can't be used to guess the control token byte-by-byte.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn authorized(headers: &HeaderMap, token: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|provided| constant_time_eq(provided, token))
+        .unwrap_or(false)
+}
+
+fn session_status() -> Value {
+    json!({ "session_active": SESSION_ACTIVE.load(Ordering::SeqCst) })
+}
+
+async fn actions_handler(
+    State(state): State<Arc<ControlState>>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    if !authorized(&headers, &state.token) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+
+    match params.get("action").map(String::as_str) {
+        Some("session_start") => {
+            if let Some(window) = state.app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            SESSION_ACTIVE.store(true, Ordering::SeqCst);
+        }
+        Some("session_end") => {
+            if let Some(window) = state.app.get_webview_window("main") {
+                let _ = window.hide();
+            }
+            SESSION_ACTIVE.store(false, Ordering::SeqCst);
+        }
+        _ => return (StatusCode::BAD_REQUEST, Json(json!({ "error": "unknown action" }))),
+    }
+
+    (StatusCode::OK, Json(session_status()))
+}
+
+async fn status_handler(
+    State(state): State<Arc<ControlState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !authorized(&headers, &state.token) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+    (StatusCode::OK, Json(session_status()))
+}
+
+/// Bind a loopback-only listener and serve the control API on it.
+pub fn spawn(app: AppHandle, token: String) {
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::bind("127.0.0.1:0").await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("[Tauri] Failed to bind control API listener: {}", e);
+                return;
+            }
+        };
+
+        if let Ok(addr) = listener.local_addr() {
+            println!("[Tauri] Control API listening on {}", addr);
+            *CONTROL_ENDPOINT.lock().unwrap() = Some(addr.to_string());
+        }
+
+        let state = Arc::new(ControlState { app, token });
+        let router = Router::new()
+            .route("/actions", get(actions_handler))
+            .route("/status", get(status_handler))
+            .with_state(state);
+
+        if let Err(e) = axum::serve(listener, router).await {
+            eprintln!("[Tauri] Control API server error: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_time_eq_matches_equal_strings() {
+        assert!(constant_time_eq("s3cr3t-token", "s3cr3t-token"));
+    }
+
+    #[test]
+    fn constant_time_eq_rejects_different_strings() {
+        assert!(!constant_time_eq("s3cr3t-token", "wrong-token"));
+        assert!(!constant_time_eq("short", "much-longer-token"));
+        assert!(!constant_time_eq("", "token"));
+    }
+
+    fn headers_with_auth(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn authorized_accepts_matching_bearer_token() {
+        let headers = headers_with_auth("Bearer abc123");
+        assert!(authorized(&headers, "abc123"));
+    }
+
+    #[test]
+    fn authorized_rejects_wrong_token() {
+        let headers = headers_with_auth("Bearer wrong");
+        assert!(!authorized(&headers, "abc123"));
+    }
+
+    #[test]
+    fn authorized_rejects_missing_header() {
+        assert!(!authorized(&HeaderMap::new(), "abc123"));
+    }
+
+    #[test]
+    fn authorized_rejects_non_bearer_scheme() {
+        let headers = headers_with_auth("Basic abc123");
+        assert!(!authorized(&headers, "abc123"));
+    }
+}
@@ -0,0 +1,55 @@
+//! In-process transport for the sidecar's health check, reachable through a
+//! Tauri custom URI scheme (`oni://...`) routed directly through an
+//! in-process `axum::Router`, skipping the TCP round-trip entirely.
+//!
+//! This only wires up `/api/oni/status`. The real API routes live in
+//! `server.mjs` and aren't reimplemented here, so this is NOT exposed as a
+//! selectable alternative to the Node sidecar in `main.rs` — there is no
+//! `use_sidecar`-style toggle pointing at it. It exists as a building block
+//! for a future full port of the API routes; until that lands, treat it as
+//! health-probe-only.
+
+use axum::body::Body;
+use axum::http::Request as AxumRequest;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::json;
+use tower::ServiceExt;
+
+/// Build the in-process router. Currently health-only: it answers
+/// `/api/oni/status` so the embedded transport has something to probe, but it
+/// does not yet mirror the rest of the routes `server.mjs` exposes.
+pub fn build_router() -> Router {
+    Router::new().route("/api/oni/status", get(oni_status))
+}
+
+async fn oni_status() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok", "embedded": true }))
+}
+
+/// Drive a Tauri custom-protocol request through the embedded router,
+/// converting between `tauri::http` and `axum::http` request/response types
+/// on the way in and out.
+pub async fn handle_request(
+    router: Router,
+    request: tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    let (parts, body) = request.into_parts();
+    let axum_request = AxumRequest::from_parts(parts, Body::from(body));
+
+    let axum_response = match router.oneshot(axum_request).await {
+        Ok(response) => response,
+        Err(_) => {
+            return tauri::http::Response::builder()
+                .status(500)
+                .body(Vec::new())
+                .unwrap();
+        }
+    };
+
+    let (parts, body) = axum_response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .unwrap_or_default();
+    tauri::http::Response::from_parts(parts, bytes.to_vec())
+}
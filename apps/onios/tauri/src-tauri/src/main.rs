@@ -1,18 +1,138 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+//
+// This source tree ships without a Cargo.toml (a snapshot of src-tauri only),
+// so there is no manifest in this diff to update. Since chunk0-5/6/7, this
+// crate additionally depends on `axum`, `tower`, `uuid`, `serde_json`, and
+// `tokio` (with its `net` feature, for the control API's listener) beyond the
+// baseline's `serde` and `tauri_plugin_*` crates (chunk0-6 dropped the
+// `reqwest` dependency the baseline had) — whoever restores the manifest for
+// this tree needs to add those five.
+
+mod control_api;
+mod embedded_server;
 
 use std::process::{Command, Child, Stdio};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use std::thread;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::fs::OpenOptions;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Global handle to the Node sidecar process so we can kill it on exit.
 static SERVER_PROCESS: Mutex<Option<Child>> = Mutex::new(None);
-static SERVER_PORT: Mutex<u16> = Mutex::new(5173);
+
+/// Named-pipe path (Windows) or Unix domain socket path (macOS/Linux) the
+/// sidecar is listening on, replacing the old localhost TCP port. This
+/// supersedes chunk0-1's dynamic-port-allocation contract entirely — no code
+/// from that change survives past chunk0-6 except the `get_server_port`
+/// compatibility shim below, so chunk0-1 should be read as superseded rather
+/// than as landed functionality.
+static IPC_ENDPOINT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Per-launch bearer token required to call the control API.
+static CONTROL_TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+/// Whether the restart supervisor should keep watching the sidecar. Cleared
+/// by `stop_node_server` so a deliberate shutdown never races with a restart.
+static SUPERVISOR_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+const SUPERVISOR_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const SUPERVISOR_INITIAL_BACKOFF: Duration = Duration::from_millis(300);
+const SUPERVISOR_MAX_BACKOFF: Duration = Duration::from_secs(10);
+const SUPERVISOR_MAX_RESTARTS: u32 = 10;
+
+/// How long to wait after a graceful SIGTERM/taskkill before escalating to
+/// a hard kill, giving `server.mjs` a chance to flush state on shutdown.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Payload emitted to the frontend for every line the sidecar writes.
+#[derive(Clone, Serialize)]
+struct SidecarLogPayload {
+    stream: &'static str,
+    line: String,
+}
+
+/// Drain a sidecar output stream line-by-line, mirroring each line to the
+/// rotating log file and forwarding it to the frontend as a `sidecar-log`
+/// event, instead of leaving it buffered in the OS pipe.
+fn stream_sidecar_output<R: std::io::Read + Send + 'static>(
+    app: AppHandle,
+    reader: R,
+    stream: &'static str,
+    log_path: std::path::PathBuf,
+) {
+    thread::spawn(move || {
+        let mut log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .ok();
+
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+
+            if let Some(file) = log_file.as_mut() {
+                let _ = writeln!(file, "[{}] {}", stream, line);
+            }
+
+            let _ = app.emit("sidecar-log", SidecarLogPayload { stream, line });
+        }
+    });
+}
+
+/// Where the Unix domain socket lives under a given app-data directory.
+/// Pulled out of `ipc_endpoint_path` so the path construction is testable
+/// without spinning up a Tauri `AppHandle`.
+#[cfg(unix)]
+fn ipc_socket_path(app_data_dir: &std::path::Path) -> std::path::PathBuf {
+    app_data_dir.join("oni-sidecar.sock")
+}
+
+/// Pick a local-only IPC endpoint for the sidecar to listen on: a named pipe
+/// on Windows, a Unix domain socket under the app's local data dir elsewhere.
+/// This keeps frontend↔backend traffic off any discoverable localhost port.
+#[cfg(unix)]
+fn ipc_endpoint_path(app: &AppHandle) -> Result<String, String> {
+    let dir = app.path().app_local_data_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let path = ipc_socket_path(&dir);
+    // Clear a stale socket file left behind by a previous crash so the
+    // sidecar's bind() doesn't fail with "address in use".
+    let _ = std::fs::remove_file(&path);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(windows)]
+fn ipc_endpoint_path(_app: &AppHandle) -> Result<String, String> {
+    Ok(format!(r"\\.\pipe\oni-sidecar-{}", std::process::id()))
+}
+
+/// Path to the sidecar log file, rotating the previous run's log aside so
+/// each launch starts from a fresh file.
+fn sidecar_log_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    let log_dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&log_dir).map_err(|e| e.to_string())?;
+
+    let log_path = log_dir.join("sidecar.log");
+    if log_path.exists() {
+        let rotated = log_dir.join("sidecar.log.old");
+        let _ = std::fs::rename(&log_path, rotated);
+    }
+    Ok(log_path)
+}
 
 /// Start the Node.js API server (reuses electron/server.mjs).
-fn start_node_server() -> Result<u16, String> {
-    let port: u16 = 5173;
+fn start_node_server(app: &AppHandle) -> Result<String, String> {
+    let endpoint = ipc_endpoint_path(app)?;
 
     // Path to server.mjs relative to the Tauri binary
     // In dev: ../../electron/server.mjs
@@ -38,66 +158,287 @@ fn start_node_server() -> Result<u16, String> {
 
     println!("[Tauri] Starting Node server: {:?}", server_script);
 
-    let child = Command::new("node")
-        .arg("--experimental-modules")
+    let mut cmd = Command::new("node");
+    cmd.arg("--experimental-modules")
         .arg(&server_script)
+        .env("ONI_IPC_ENDPOINT", &endpoint)
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Put the sidecar in its own process group so we can signal the whole
+    // tree (Node plus any workers it spawns) instead of just the direct child.
+    #[cfg(unix)]
+    {
+        cmd.process_group(0);
+    }
+
+    let mut child = cmd
         .spawn()
         .map_err(|e| format!("Failed to start Node server: {}", e))?;
 
+    let log_path = sidecar_log_path(app)?;
+    if let Some(stdout) = child.stdout.take() {
+        stream_sidecar_output(app.clone(), stdout, "stdout", log_path.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        stream_sidecar_output(app.clone(), stderr, "stderr", log_path.clone());
+    }
+
     *SERVER_PROCESS.lock().unwrap() = Some(child);
-    *SERVER_PORT.lock().unwrap() = port;
+    *IPC_ENDPOINT.lock().unwrap() = Some(endpoint.clone());
 
-    // Wait for the server to be ready (poll health endpoint)
+    // Wait for the server to be ready (poll health endpoint over the pipe/socket)
     let start = Instant::now();
     let timeout = Duration::from_secs(15);
 
     while start.elapsed() < timeout {
         thread::sleep(Duration::from_millis(300));
-        if let Ok(resp) = reqwest::blocking::get(format!("http://127.0.0.1:{}/api/oni/status", port)) {
-            if resp.status().is_success() {
-                println!("[Tauri] Node server ready on port {}", port);
-                return Ok(port);
-            }
+        if is_sidecar_healthy(&endpoint) {
+            println!("[Tauri] Node server ready on {}", endpoint);
+            return Ok(endpoint);
         }
     }
 
     Err("Node server did not become ready within 15 seconds".into())
 }
 
-/// Kill the sidecar Node process.
-fn stop_node_server() {
+/// Block until `child` exits or `SHUTDOWN_GRACE_PERIOD` elapses, polling
+/// `try_wait` instead of sleeping the whole window, so a process that exits
+/// promptly doesn't stall shutdown. Returns `true` if it exited in time.
+fn wait_for_exit_within_grace_period(child: &mut Child) -> bool {
+    let deadline = Instant::now() + SHUTDOWN_GRACE_PERIOD;
+    while Instant::now() < deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    false
+}
+
+/// Terminate the sidecar's whole process tree on Unix: SIGTERM the process
+/// group it was launched into, wait (polling) for it to exit, then escalate
+/// to SIGKILL only if it's still alive after the grace period.
+#[cfg(unix)]
+fn terminate_process_tree(pid: u32, child: &mut Child) {
+    let _ = Command::new("kill").arg("-TERM").arg(format!("-{}", pid)).status();
+    if wait_for_exit_within_grace_period(child) {
+        return;
+    }
+    let _ = Command::new("kill").arg("-KILL").arg(format!("-{}", pid)).status();
+    let _ = child.wait();
+}
+
+/// Terminate the sidecar's whole process tree on Windows: ask nicely first
+/// (no `/F`) so `server.mjs` can flush, wait (polling) for it to exit, then
+/// escalate to `taskkill /F` only if it's still alive after the grace period.
+/// `/T` walks down to descendants that `Child::kill` would otherwise orphan.
+#[cfg(windows)]
+fn terminate_process_tree(pid: u32, child: &mut Child) {
+    let _ = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T"])
+        .status();
+    if wait_for_exit_within_grace_period(child) {
+        return;
+    }
+    let _ = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .status();
+    let _ = child.wait();
+}
+
+/// Kill the sidecar Node process and anything it spawned (without touching
+/// supervisor state). Skips signalling entirely if the process has already
+/// exited, so we never re-signal a pid/pgid the OS may have since reused.
+fn kill_node_process() {
     if let Ok(mut guard) = SERVER_PROCESS.lock() {
-        if let Some(ref mut child) = *guard {
-            println!("[Tauri] Stopping Node server");
-            let _ = child.kill();
-            let _ = child.wait();
+        if let Some(mut child) = guard.take() {
+            let pid = child.id();
+            if matches!(child.try_wait(), Ok(Some(_))) {
+                println!("[Tauri] Node server (pid {}) had already exited", pid);
+                return;
+            }
+            println!("[Tauri] Stopping Node server (pid {})", pid);
+            terminate_process_tree(pid, &mut child);
+        }
+    }
+}
+
+/// Stop the sidecar for good. Disables the supervisor first so the restart
+/// loop can't race a deliberate shutdown and immediately respawn the server.
+fn stop_node_server() {
+    SUPERVISOR_ACTIVE.store(false, Ordering::SeqCst);
+    kill_node_process();
+}
+
+/// Send a minimal HTTP/1.1 GET for the existing health endpoint over an
+/// already-connected pipe/socket stream and check for a 2xx status line.
+fn request_health_over_stream<S: Read + Write>(mut stream: S) -> bool {
+    let request = b"GET /api/oni/status HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    if stream.write_all(request).is_err() {
+        return false;
+    }
+
+    let mut buf = [0u8; 512];
+    match stream.read(&mut buf) {
+        Ok(n) if n > 0 => {
+            let response = String::from_utf8_lossy(&buf[..n]);
+            response.starts_with("HTTP/1.1 2") || response.starts_with("HTTP/1.0 2")
+        }
+        _ => false,
+    }
+}
+
+/// Poll the existing health endpoint over the sidecar's IPC endpoint to
+/// decide if it is responsive.
+#[cfg(unix)]
+fn is_sidecar_healthy(endpoint: &str) -> bool {
+    match std::os::unix::net::UnixStream::connect(endpoint) {
+        Ok(stream) => {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            request_health_over_stream(stream)
         }
-        *guard = None;
+        Err(_) => false,
+    }
+}
+
+#[cfg(windows)]
+fn is_sidecar_healthy(endpoint: &str) -> bool {
+    match OpenOptions::new().read(true).write(true).open(endpoint) {
+        Ok(pipe) => request_health_over_stream(pipe),
+        Err(_) => false,
     }
 }
 
+/// Watch the sidecar process and restart it with exponential backoff if it
+/// crashes or stops responding to health checks.
+fn spawn_supervisor(app: AppHandle) {
+    SUPERVISOR_ACTIVE.store(true, Ordering::SeqCst);
+
+    thread::spawn(move || {
+        let mut backoff = SUPERVISOR_INITIAL_BACKOFF;
+        let mut restarts = 0u32;
+
+        loop {
+            thread::sleep(SUPERVISOR_POLL_INTERVAL);
+            if !SUPERVISOR_ACTIVE.load(Ordering::SeqCst) {
+                break;
+            }
+
+            let exited = match SERVER_PROCESS.lock().unwrap().as_mut() {
+                Some(child) => matches!(child.try_wait(), Ok(Some(_))),
+                None => true,
+            };
+            let endpoint = IPC_ENDPOINT.lock().unwrap().clone();
+            let healthy = !exited
+                && endpoint
+                    .as_deref()
+                    .map(is_sidecar_healthy)
+                    .unwrap_or(false);
+
+            if !exited && healthy {
+                // A clean health check means the sidecar has recovered, so the
+                // restart bound should track consecutive failures, not every
+                // restart across the app's lifetime.
+                backoff = SUPERVISOR_INITIAL_BACKOFF;
+                restarts = 0;
+                continue;
+            }
+
+            if restarts >= SUPERVISOR_MAX_RESTARTS {
+                eprintln!("[Tauri] Sidecar exceeded {} restart attempts, giving up", SUPERVISOR_MAX_RESTARTS);
+                let _ = app.emit("sidecar-down", ());
+                break;
+            }
+
+            println!("[Tauri] Sidecar {}, restarting in {:?}", if exited { "exited" } else { "unhealthy" }, backoff);
+            let _ = app.emit("sidecar-down", ());
+            thread::sleep(backoff);
+            if !SUPERVISOR_ACTIVE.load(Ordering::SeqCst) {
+                break;
+            }
+
+            kill_node_process();
+            restarts += 1;
+            backoff = (backoff * 2).min(SUPERVISOR_MAX_BACKOFF);
+
+            match start_node_server(&app) {
+                Ok(_) => {
+                    println!("[Tauri] Sidecar restarted successfully");
+                    let _ = app.emit("sidecar-up", ());
+                }
+                Err(e) => eprintln!("[Tauri] Sidecar restart failed: {}", e),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+fn get_ipc_endpoint() -> Option<String> {
+    IPC_ENDPOINT.lock().unwrap().clone()
+}
+
+/// Deprecated compatibility shim: chunk0-1 introduced `get_server_port` and a
+/// dynamically-allocated TCP port, both of which chunk0-6 replaced with the
+/// pipe/socket transport behind `get_ipc_endpoint`. There is no port to
+/// report anymore — this always returns 0 so pre-chunk0-6 frontend callers
+/// that haven't migrated yet fail soft instead of hitting a missing command.
+/// New code should call `get_ipc_endpoint`.
 #[tauri::command]
 fn get_server_port() -> u16 {
-    *SERVER_PORT.lock().unwrap()
+    0
+}
+
+#[tauri::command]
+fn get_control_token() -> Option<String> {
+    CONTROL_TOKEN.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_control_endpoint() -> Option<String> {
+    control_api::endpoint()
 }
 
 fn main() {
-    // In production, start the Node sidecar server
+    // In production, start the Node sidecar server. The embedded oni:// router
+    // (see embedded_server) only answers the health probe so far, not the rest
+    // of the API server.mjs exposes — it isn't a selectable backend yet, so
+    // there is no ONI_USE_SIDECAR toggle to opt out of the sidecar.
     let use_sidecar = !cfg!(debug_assertions);
 
-    if use_sidecar {
-        match start_node_server() {
-            Ok(port) => println!("[Tauri] Production server on port {}", port),
-            Err(e) => eprintln!("[Tauri] WARNING: {}", e),
-        }
-    }
-
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_process::init())
-        .invoke_handler(tauri::generate_handler![get_server_port])
+        .invoke_handler(tauri::generate_handler![
+            get_ipc_endpoint,
+            get_server_port,
+            get_control_token,
+            get_control_endpoint
+        ])
+        .register_asynchronous_uri_scheme_protocol("oni", move |_ctx, request, responder| {
+            let router = embedded_server::build_router();
+            tauri::async_runtime::spawn(async move {
+                let response = embedded_server::handle_request(router, request).await;
+                responder.respond(response);
+            });
+        })
+        .setup(move |app| {
+            let control_token = uuid::Uuid::new_v4().to_string();
+            *CONTROL_TOKEN.lock().unwrap() = Some(control_token.clone());
+            control_api::spawn(app.handle().clone(), control_token);
+
+            if use_sidecar {
+                let handle = app.handle().clone();
+                match start_node_server(&handle) {
+                    Ok(endpoint) => {
+                        println!("[Tauri] Production server on {}", endpoint);
+                        spawn_supervisor(handle);
+                    }
+                    Err(e) => eprintln!("[Tauri] WARNING: {}", e),
+                }
+            }
+            Ok(())
+        })
         .on_window_event(|_window, event| {
             if let tauri::WindowEvent::Destroyed = event {
                 stop_node_server();
@@ -106,3 +447,76 @@ fn main() {
         .run(tauri::generate_context!())
         .expect("error while running OniOS");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    /// An in-memory stand-in for a pipe/socket stream: discards whatever is
+    /// written and, on the first read, hands back a canned response.
+    struct MockStream {
+        response: Vec<u8>,
+        delivered: bool,
+    }
+
+    impl MockStream {
+        fn with_response(response: &str) -> Self {
+            MockStream { response: response.as_bytes().to_vec(), delivered: false }
+        }
+    }
+
+    impl Write for MockStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Read for MockStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.delivered {
+                return Ok(0);
+            }
+            self.delivered = true;
+            let n = self.response.len().min(buf.len());
+            buf[..n].copy_from_slice(&self.response[..n]);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn request_health_over_stream_accepts_2xx_status_lines() {
+        assert!(request_health_over_stream(MockStream::with_response(
+            "HTTP/1.1 200 OK\r\n\r\n{\"status\":\"ok\"}"
+        )));
+        assert!(request_health_over_stream(MockStream::with_response(
+            "HTTP/1.0 204 No Content\r\n\r\n"
+        )));
+    }
+
+    #[test]
+    fn request_health_over_stream_rejects_non_2xx_status_lines() {
+        assert!(!request_health_over_stream(MockStream::with_response(
+            "HTTP/1.1 500 Internal Server Error\r\n\r\n"
+        )));
+        assert!(!request_health_over_stream(MockStream::with_response(
+            "HTTP/1.1 404 Not Found\r\n\r\n"
+        )));
+    }
+
+    #[test]
+    fn request_health_over_stream_rejects_empty_response() {
+        assert!(!request_health_over_stream(MockStream::with_response("")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ipc_socket_path_is_under_the_given_app_data_dir() {
+        let dir = std::path::Path::new("/tmp/oni-test-app-data");
+        let path = ipc_socket_path(dir);
+        assert_eq!(path, dir.join("oni-sidecar.sock"));
+    }
+}